@@ -0,0 +1,222 @@
+//! Archive support for storing many flights in a single file.
+//!
+//! Fleet loggers accumulate flights that repeat the same [`PlaneInformation`] record (the
+//! same aircraft flying many times), so per-file compression alone wastes space on that
+//! repetition. A [`FlygArchive`] deduplicates those records into a shared table and has each
+//! flight reference its plane through a stable index instead of embedding it again.
+
+use crate::{
+    codec_from_target_extension, deserialize_with_codec, detect_codec, serialize_with_codec,
+    wrap_error, FlygFlight, FlygFormatError, FuelRecord, PlaneInformation, Times,
+};
+use serde::{Deserialize, Serialize};
+
+/// A flight whose embedded [`PlaneInformation`] has been replaced with an index into the
+/// shared plane table of the enclosing [`FlygArchive`].
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactFlight {
+    /// The index of this flight's plane within the archive's `planes` table.
+    pub plane_index: u32,
+    /// The touch down speed of the plane in feet per second.
+    pub landing_speed: f32,
+    /// The important time recording of the flight.
+    pub times: Times,
+    /// All fuel related dynamic information during the flight.
+    pub fuel_records: Vec<FuelRecord>,
+}
+
+/// An archive of many flights sharing a deduplicated table of [`PlaneInformation`] records.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlygArchive {
+    /// The deduplicated plane records referenced by `flights`.
+    pub planes: Vec<PlaneInformation>,
+    /// The flights contained in this archive, each referencing its plane by index.
+    pub flights: Vec<CompactFlight>,
+}
+
+impl FlygArchive {
+    /// Builds an archive from a list of flights, deduplicating plane records by structural
+    /// equality.
+    ///
+    /// # Panics
+    /// Panics if the number of distinct planes exceeds `u32::MAX`.
+    #[must_use]
+    pub fn from_flights(flights: Vec<FlygFlight>) -> Self {
+        let mut planes: Vec<PlaneInformation> = Vec::new();
+        let mut compact_flights = Vec::with_capacity(flights.len());
+
+        for flight in flights {
+            let plane_index = planes
+                .iter()
+                .position(|plane| plane == &flight.plane_information)
+                .unwrap_or_else(|| {
+                    planes.push(flight.plane_information);
+                    planes.len() - 1
+                });
+            compact_flights.push(CompactFlight {
+                plane_index: u32::try_from(plane_index)
+                    .expect("plane table should never exceed u32::MAX entries"),
+                landing_speed: flight.landing_speed,
+                times: flight.times,
+                fuel_records: flight.fuel_records,
+            });
+        }
+
+        FlygArchive {
+            planes,
+            flights: compact_flights,
+        }
+    }
+
+    /// Reconstructs every flight in this archive lazily, resolving each [`CompactFlight`]'s
+    /// plane index back into a full [`FlygFlight`].
+    ///
+    /// # Errors
+    /// If a flight references a plane index that does not exist in the archive's `planes`
+    /// table, a [`FlygFormatError::FileFormatNotRecognized`] error is yielded for that entry.
+    pub fn flights(&self) -> impl Iterator<Item = Result<FlygFlight, FlygFormatError>> + '_ {
+        self.flights.iter().map(move |compact| {
+            let plane_information = self
+                .planes
+                .get(compact.plane_index as usize)
+                .cloned()
+                .ok_or_else(|| {
+                    log::warn!(
+                        "resolving flight failed: plane index {} does not exist in the archive's plane table",
+                        compact.plane_index
+                    );
+                    FlygFormatError::FileFormatNotRecognized(None)
+                })?;
+            Ok(FlygFlight {
+                plane_information,
+                landing_speed: compact.landing_speed,
+                times: compact.times.clone(),
+                fuel_records: compact.fuel_records.clone(),
+            })
+        })
+    }
+}
+
+/// Load a [`FlygArchive`] from a file, detecting the container format from its magic bytes
+/// just like [`crate::load_flight_information_from_file`] does for individual flights.
+///
+/// # Errors
+/// If the supplied file could not be opened, a [`FlygFormatError::CouldNotOpenFile`] error
+/// will be returned. If its content is not a recognized archive, a
+/// [`FlygFormatError::FileFormatNotRecognized`] error will be returned. If the detected codec
+/// was not compiled into this build, a [`FlygFormatError::DecompressionFailed`] error will be
+/// returned instead.
+pub fn load_archive(filename: &str) -> Result<FlygArchive, FlygFormatError> {
+    use std::io::BufReader;
+
+    log::debug!("opening archive file '{filename}'");
+    match std::fs::File::open(filename) {
+        Ok(file_handle) => {
+            let buffered_reader = BufReader::new(file_handle);
+            let (codec, rechained) = detect_codec(buffered_reader).map_err(|error| {
+                wrap_error(
+                    "detecting container format",
+                    error,
+                    FlygFormatError::CouldNotOpenFile,
+                )
+            })?;
+            deserialize_with_codec(codec, rechained)
+        }
+        Err(error) => Err(wrap_error(
+            "opening file",
+            error,
+            FlygFormatError::CouldNotOpenFile,
+        )),
+    }
+}
+
+/// Save a [`FlygArchive`] to a file, deriving the container format from its extension just
+/// like [`crate::save_flight_information_to_file`] does for individual flights.
+///
+/// # Errors
+/// If the archive could not be serialized or the target file could not be created or written
+/// to, a [`FlygFormatError::CouldNotWriteFile`] error will be returned.
+pub fn save_archive(archive: &FlygArchive, filename: &str) -> Result<(), FlygFormatError> {
+    let codec = codec_from_target_extension(filename);
+    log::debug!("writing archive to '{filename}' using codec {codec:?}");
+    let file_handle = std::fs::File::create(filename)
+        .map_err(|error| wrap_error("creating file", error, FlygFormatError::CouldNotWriteFile))?;
+    serialize_with_codec(archive, codec, file_handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FlygArchive;
+    use crate::{FlygFlight, FlygFormatError, FuelRecord, PlaneInformation, Times};
+
+    fn build_flight(plane_name: &str) -> FlygFlight {
+        FlygFlight {
+            plane_information: PlaneInformation {
+                name: String::from(plane_name),
+                fuel_capacity: 56,
+                number_of_engines: 1,
+                fuel_weight: 6.01,
+                unusable_fuel_quantity: 1.5,
+            },
+            landing_speed: 65.0,
+            times: Times {
+                block_off_time: String::from("2024-01-01T10:00:00Z"),
+                block_on_time: String::from("2024-01-01T11:30:00Z"),
+                landing_time: String::from("2024-01-01T11:25:00Z"),
+                takeoff_time: String::from("2024-01-01T10:05:00Z"),
+            },
+            fuel_records: vec![FuelRecord { fuel_quantity: 40.0 }],
+        }
+    }
+
+    #[test]
+    fn identical_planes_are_deduplicated_into_a_single_table_entry() {
+        // two flights performed with the very same plane
+        let flights = vec![build_flight("Cessna 172"), build_flight("Cessna 172")];
+
+        // build the archive and check that only one plane record was kept
+        let archive = FlygArchive::from_flights(flights);
+        assert_eq!(1, archive.planes.len());
+        assert_eq!(2, archive.flights.len());
+    }
+
+    #[test]
+    fn flights_can_be_reconstructed_from_an_archive() {
+        // two flights performed with different planes
+        let flights = vec![build_flight("Cessna 172"), build_flight("Piper PA-28")];
+
+        // build the archive and reconstruct the flights from it again
+        let archive = FlygArchive::from_flights(flights);
+        let reconstructed: Result<Vec<FlygFlight>, FlygFormatError> = archive.flights().collect();
+        let reconstructed = reconstructed.unwrap();
+
+        assert_eq!(2, reconstructed.len());
+        assert_eq!("Cessna 172", reconstructed[0].plane_information.name);
+        assert_eq!("Piper PA-28", reconstructed[1].plane_information.name);
+    }
+
+    #[test]
+    fn an_out_of_range_plane_index_is_reported_as_not_recognized() {
+        // an archive whose only flight references a plane index that does not exist
+        let archive = FlygArchive {
+            planes: vec![],
+            flights: vec![super::CompactFlight {
+                plane_index: 0,
+                landing_speed: 65.0,
+                times: build_flight("Cessna 172").times,
+                fuel_records: vec![],
+            }],
+        };
+
+        // reconstructing the flight should surface the dangling index as a format error
+        let mut reconstructed: Vec<Result<FlygFlight, FlygFormatError>> =
+            archive.flights().collect();
+        assert_eq!(1, reconstructed.len());
+        match reconstructed.pop().unwrap() {
+            Err(error) => assert_eq!(FlygFormatError::FileFormatNotRecognized(None), error),
+            Ok(_) => panic!("expected a FileFormatNotRecognized error"),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Parallel block-gzip (BGZF-style) encoding for flights with very large `fuel_records` logs.
+//!
+//! Independent gzip members concatenate into a single byte stream the same way the BGZF
+//! container format (used by tools such as samtools) does, which lets every block be produced
+//! on its own thread instead of serializing the whole flight through one gzip stream. The
+//! plain gzip codec behind [`crate::load_flight_information_from_reader`] can decode that
+//! stream member-by-member just fine, but it does so on a single thread; this module provides
+//! a multistream-aware reader that walks member boundaries instead and decompresses them
+//! across a thread pool. Locating those boundaries decodes every member once on a single
+//! thread, so the parallel pass decodes the data a second time and a load through this reader
+//! is not faster than the plain single-threaded one.
+
+use crate::{wrap_error, FlygFlight, FlygFormatError};
+use std::io::{Cursor, Read, Write};
+
+/// The default size (in bytes) of each independently compressed BGZF block.
+pub const DEFAULT_BLOCK_SIZE: usize = 64 * 1024;
+
+/// Builder controlling the block size and thread count used by [`BgzfWriteBuilder::write`].
+pub struct BgzfWriteBuilder {
+    block_size: usize,
+    thread_count: usize,
+}
+
+impl Default for BgzfWriteBuilder {
+    fn default() -> Self {
+        let thread_count = std::thread::available_parallelism()
+            .map_or(1, std::num::NonZeroUsize::get);
+        BgzfWriteBuilder {
+            block_size: DEFAULT_BLOCK_SIZE,
+            thread_count,
+        }
+    }
+}
+
+impl BgzfWriteBuilder {
+    /// Creates a new [`BgzfWriteBuilder`] with [`DEFAULT_BLOCK_SIZE`] blocks and one thread
+    /// per available CPU.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the size (in bytes) of each independently compressed block.
+    #[must_use]
+    pub fn block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size.max(1);
+        self
+    }
+
+    /// Overrides how many blocks are compressed concurrently.
+    #[must_use]
+    pub fn thread_count(mut self, thread_count: usize) -> Self {
+        self.thread_count = thread_count.max(1);
+        self
+    }
+
+    /// Serializes `flight` and writes it to `writer` as a block-gzip container: the
+    /// serialized JSON is split into `block_size`-sized chunks, each gzip-encoded
+    /// independently across up to `thread_count` threads, then written out in order. Because
+    /// every block is a complete, valid gzip member, the result is an ordinary concatenated
+    /// gzip stream; it can be read back either with [`read_bgzf_flight`] for multithreaded
+    /// decompression, or with [`crate::load_flight_information_from_reader`] for a plain
+    /// single-threaded read.
+    ///
+    /// # Errors
+    /// If `flight` could not be serialized or a block could not be compressed or written, a
+    /// [`FlygFormatError::CouldNotWriteFile`] error will be returned.
+    ///
+    /// # Panics
+    /// Panics if a compression thread panicked.
+    pub fn write<W: Write>(
+        self,
+        flight: &FlygFlight,
+        mut writer: W,
+    ) -> Result<(), FlygFormatError> {
+        log::debug!("serializing flight for BGZF encoding");
+        let serialized = serde_json::to_vec(flight).map_err(|error| {
+            wrap_error(
+                "serializing JSON",
+                error,
+                FlygFormatError::CouldNotWriteFile,
+            )
+        })?;
+
+        log::debug!(
+            "compressing {} bytes across {}-byte blocks on up to {} threads",
+            serialized.len(),
+            self.block_size,
+            self.thread_count
+        );
+        for batch in serialized.chunks(self.block_size).collect::<Vec<_>>().chunks(self.thread_count) {
+            let compressed_blocks: Vec<std::io::Result<Vec<u8>>> = std::thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|chunk| scope.spawn(move || compress_block(chunk)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("gzip compression thread panicked"))
+                    .collect()
+            });
+
+            for compressed in compressed_blocks {
+                let compressed = compressed.map_err(|error| {
+                    wrap_error(
+                        "compressing BGZF block",
+                        error,
+                        FlygFormatError::CouldNotWriteFile,
+                    )
+                })?;
+                writer.write_all(&compressed).map_err(|error| {
+                    wrap_error(
+                        "writing BGZF block",
+                        error,
+                        FlygFormatError::CouldNotWriteFile,
+                    )
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn compress_block(chunk: &[u8]) -> std::io::Result<Vec<u8>> {
+    use libflate::gzip::Encoder;
+
+    let mut encoder = Encoder::new(Vec::new())?;
+    encoder.write_all(chunk)?;
+    encoder.finish().into_result()
+}
+
+fn decompress_block(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    use libflate::gzip::Decoder;
+
+    let mut decoder = Decoder::new(bytes)?;
+    let mut decompressed = Vec::new();
+    decoder.read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Walks `data` member-by-member and returns the byte range covered by each gzip member,
+/// relying on the fact that decoding one member through a [`Cursor`] leaves the cursor
+/// positioned exactly at the start of the next one.
+fn gzip_member_ranges(data: &[u8]) -> std::io::Result<Vec<std::ops::Range<usize>>> {
+    use libflate::gzip::Decoder;
+
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let mut cursor = Cursor::new(&data[offset..]);
+        {
+            let mut decoder = Decoder::new(&mut cursor)?;
+            std::io::copy(&mut decoder, &mut std::io::sink())?;
+        }
+        let consumed = usize::try_from(cursor.position()).unwrap_or(data.len() - offset);
+        ranges.push(offset..offset + consumed);
+        offset += consumed;
+    }
+    Ok(ranges)
+}
+
+/// Writes `flight` as a BGZF container to `writer`, using [`DEFAULT_BLOCK_SIZE`] blocks and
+/// one thread per available CPU.
+///
+/// # Errors
+/// See [`BgzfWriteBuilder::write`].
+pub fn write_bgzf_flight<W: Write>(
+    flight: &FlygFlight,
+    writer: W,
+) -> Result<(), FlygFormatError> {
+    BgzfWriteBuilder::new().write(flight, writer)
+}
+
+/// Reads and deserializes a flight from a BGZF (or any other concatenated multi-member gzip)
+/// byte buffer, decompressing up to `thread_count` blocks at a time. The member boundaries
+/// are found by first decoding every member sequentially on the calling thread.
+///
+/// # Errors
+/// If any member could not be decompressed, a [`FlygFormatError::DecompressionFailed`] error
+/// will be returned. If the decompressed content is not a recognized flight, a
+/// [`FlygFormatError::FileFormatNotRecognized`] error will be returned.
+///
+/// # Panics
+/// Panics if a decompression thread panicked.
+pub fn read_bgzf_flight(data: &[u8], thread_count: usize) -> Result<FlygFlight, FlygFormatError> {
+    log::debug!("locating BGZF member boundaries in {} bytes", data.len());
+    let ranges = gzip_member_ranges(data).map_err(|error| {
+        wrap_error(
+            "locating BGZF member boundaries",
+            error,
+            FlygFormatError::DecompressionFailed,
+        )
+    })?;
+    let thread_count = thread_count.max(1);
+    let mut decompressed = Vec::new();
+
+    log::debug!(
+        "decompressing {} BGZF members on up to {thread_count} threads",
+        ranges.len()
+    );
+    for batch in ranges.chunks(thread_count) {
+        let blocks: Vec<std::io::Result<Vec<u8>>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = batch
+                .iter()
+                .map(|range| {
+                    let slice = &data[range.clone()];
+                    scope.spawn(move || decompress_block(slice))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("gzip decompression thread panicked"))
+                .collect()
+        });
+
+        for block in blocks {
+            let block = block.map_err(|error| {
+                wrap_error(
+                    "decompressing BGZF member",
+                    error,
+                    FlygFormatError::DecompressionFailed,
+                )
+            })?;
+            decompressed.extend_from_slice(&block);
+        }
+    }
+
+    serde_json::from_slice(&decompressed).map_err(|error| {
+        wrap_error(
+            "deserializing JSON",
+            error,
+            FlygFormatError::FileFormatNotRecognized,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_bgzf_flight, BgzfWriteBuilder};
+    use crate::{FlygFlight, FuelRecord, PlaneInformation, Times};
+
+    #[test]
+    fn writing_and_reading_a_flight_through_bgzf_round_trips() {
+        // a flight with enough fuel records to span several small blocks
+        let flight = FlygFlight {
+            plane_information: PlaneInformation {
+                name: String::from("Boeing 737"),
+                fuel_capacity: 6875,
+                number_of_engines: 2,
+                fuel_weight: 6.7,
+                unusable_fuel_quantity: 10.0,
+            },
+            landing_speed: 140.0,
+            times: Times {
+                block_off_time: String::from("2024-03-01T08:00:00Z"),
+                block_on_time: String::from("2024-03-01T10:30:00Z"),
+                landing_time: String::from("2024-03-01T10:25:00Z"),
+                takeoff_time: String::from("2024-03-01T08:10:00Z"),
+            },
+            fuel_records: (0..500u16)
+                .map(|index| FuelRecord {
+                    fuel_quantity: 6000.0 - f32::from(index),
+                })
+                .collect(),
+        };
+        let mut buffer = Vec::new();
+
+        // write the flight with tiny blocks across a few threads, then read it back
+        let write_result = BgzfWriteBuilder::new()
+            .block_size(256)
+            .thread_count(4)
+            .write(&flight, &mut buffer);
+        let read_result = read_bgzf_flight(&buffer, 4);
+
+        // check that the round trip preserved the data
+        assert!(write_result.is_ok());
+        assert!(read_result.is_ok());
+        assert_eq!(
+            flight.fuel_records.len(),
+            read_result.unwrap().fuel_records.len()
+        );
+    }
+}
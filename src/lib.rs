@@ -1,10 +1,18 @@
 #![deny(clippy::all)]
 #![deny(clippy::pedantic)]
 
-use serde::Deserialize;
+mod archive;
+#[cfg(feature = "compression")]
+mod bgzf;
+
+pub use archive::{load_archive, save_archive, CompactFlight, FlygArchive};
+#[cfg(feature = "compression")]
+pub use bgzf::{read_bgzf_flight, write_bgzf_flight, BgzfWriteBuilder, DEFAULT_BLOCK_SIZE};
+
+use serde::{Deserialize, Serialize};
 
 /// The [`FlygFlight`] struct is the root of any recorded flight.
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FlygFlight {
     /// The static information about the used plane.
@@ -20,7 +28,7 @@ pub struct FlygFlight {
 /// All important information about the plane which was used to perform the flight, are
 /// stored in the [`PlaneInformation`] structure. This is mostly static information which is
 /// not changed during the course of the flight.
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct PlaneInformation {
     /// The name of the plane which was used to perform the flight (provided by the simulator).
@@ -37,7 +45,7 @@ pub struct PlaneInformation {
 
 /// The [`FuelRecord`] struct holds all information regarding fuel and the flight of the plane (e.g.
 /// the amount of fuel which is currently burned per hour or the remaining fuel in the planes tanks).
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FuelRecord {
     /// The remaining fuel which the plane is carrying.
@@ -50,7 +58,7 @@ pub struct FuelRecord {
 /// landing time, which is the time when the wheels contacted with ground after a flight. The last of
 /// the four options is the block-on time. This is the time the plane arrived at the final position and
 /// shut down its engines. All those times are stored in the [`Times`] data structure.
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Times {
     /// The time when the plane started taxing from the gate to runway.
@@ -64,87 +72,504 @@ pub struct Times {
 }
 
 /// The [`FlygFormatError`] enum holds all possible errors which can occur when processing a file
-/// with flight data recordings.
-#[derive(Debug, Eq, PartialEq)]
+/// with flight data recordings. Every variant carries the underlying error that caused it (if
+/// any), reachable through [`std::error::Error::source`], so callers can inspect the root cause
+/// without the set of variants itself growing for every new failure mode.
+#[derive(Debug)]
 pub enum FlygFormatError {
     /// The supplied file name could not be opened (could be permissions or an invalid file path).
-    CouldNotOpenFile,
+    CouldNotOpenFile(Option<Box<dyn std::error::Error + Send + Sync>>),
     /// The content of the supplied file could not be interpreted.
-    FileFormatNotRecognized,
+    FileFormatNotRecognized(Option<Box<dyn std::error::Error + Send + Sync>>),
     /// Could not decompress the file which was provided.
-    DecompressionFailed,
+    DecompressionFailed(Option<Box<dyn std::error::Error + Send + Sync>>),
+    /// The flight could not be serialized or the target file could not be written.
+    CouldNotWriteFile(Option<Box<dyn std::error::Error + Send + Sync>>),
 }
 
+impl PartialEq for FlygFormatError {
+    /// Two errors are considered equal if they are the same variant, regardless of whichever
+    /// source error (if any) they carry, since [`Box<dyn std::error::Error>`] has no meaningful
+    /// notion of equality.
+    fn eq(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl Eq for FlygFormatError {}
+
 impl std::fmt::Display for FlygFormatError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            FlygFormatError::CouldNotOpenFile => {
+            FlygFormatError::CouldNotOpenFile(_) => {
                 write!(f, "The supplied file could not be opened")
             }
-            FlygFormatError::FileFormatNotRecognized => {
+            FlygFormatError::FileFormatNotRecognized(_) => {
                 write!(f, "Content of supplied file is not recognized")
             }
-            FlygFormatError::DecompressionFailed => {
+            FlygFormatError::DecompressionFailed(_) => {
                 write!(f, "Could not decompress file")
             }
+            FlygFormatError::CouldNotWriteFile(_) => {
+                write!(f, "Could not write the supplied flight to the target file")
+            }
         }
     }
 }
 
-impl std::error::Error for FlygFormatError {}
+impl std::error::Error for FlygFormatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        let (FlygFormatError::CouldNotOpenFile(source)
+        | FlygFormatError::FileFormatNotRecognized(source)
+        | FlygFormatError::DecompressionFailed(source)
+        | FlygFormatError::CouldNotWriteFile(source)) = self;
+        source
+            .as_ref()
+            .map(|boxed| boxed.as_ref() as &(dyn std::error::Error + 'static))
+    }
+}
 
-#[cfg(feature = "compression")]
-fn load_flight_from_compressed_file(filename: &str) -> Result<FlygFlight, FlygFormatError> {
-    use libflate::gzip::Decoder;
+/// Logs `stage` and `error` through the [`log`] facade and wraps `error` into `variant`, so
+/// every load/save failure surfaces a detailed `warn!` record in addition to the
+/// [`FlygFormatError`] returned to the caller.
+pub(crate) fn wrap_error<E: std::error::Error + Send + Sync + 'static>(
+    stage: &str,
+    error: E,
+    variant: fn(Option<Box<dyn std::error::Error + Send + Sync>>) -> FlygFormatError,
+) -> FlygFormatError {
+    log::warn!("{stage} failed: {error}");
+    variant(Some(Box::new(error)))
+}
 
-    match std::fs::File::open(filename) {
-        Ok(file_handle) => match Decoder::new(file_handle) {
-            Ok(decoder) => match serde_json::from_reader(decoder) {
-                Ok(read_obj) => Ok(read_obj),
-                Err(_) => Err(FlygFormatError::FileFormatNotRecognized),
-            },
-            Err(_) => Err(FlygFormatError::DecompressionFailed),
-        },
-        Err(_) => Err(FlygFormatError::CouldNotOpenFile),
+/// The compression containers `flyg-format` can detect and decode. Detection of the magic
+/// number always happens regardless of which cargo features are enabled, so an unsupported
+/// codec still fails with a helpful [`FlygFormatError::DecompressionFailed`] instead of being
+/// misinterpreted as plain JSON. Decoding a given variant requires its matching cargo feature
+/// (`compression` for gzip, `zstd`, `xz` or `bzip2`) to be enabled.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum CompressionCodec {
+    /// Uncompressed JSON.
+    Plain,
+    /// A gzip container, signed off by the `1F 8B` magic number.
+    Gzip,
+    /// A zstd container, signed off by the `28 B5 2F FD` magic number.
+    Zstd,
+    /// An xz container, signed off by the `FD 37 7A 58 5A 00` magic number.
+    Xz,
+    /// A bzip2 container, signed off by the `42 5A 68` (`BZh`) magic number.
+    Bzip2,
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+const XZ_MAGIC: [u8; 6] = [0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00];
+const BZIP2_MAGIC: [u8; 3] = [0x42, 0x5a, 0x68];
+
+/// Peeks at the first few bytes of `reader`, matches them against the known container magic
+/// numbers and returns the detected [`CompressionCodec`] along with a reader which still
+/// yields the whole original stream: the peeked bytes are chained back in front of whatever
+/// is left unread.
+pub(crate) fn detect_codec<R: std::io::Read>(
+    mut reader: R,
+) -> std::io::Result<(CompressionCodec, impl std::io::Read)> {
+    use std::io::{Cursor, Read};
+
+    let mut prefix = [0u8; 6];
+    let mut bytes_read = 0;
+    while bytes_read < prefix.len() {
+        let read_this_round = reader.read(&mut prefix[bytes_read..])?;
+        if read_this_round == 0 {
+            // real EOF: the stream is shorter than the longest magic number
+            break;
+        }
+        bytes_read += read_this_round;
     }
+    let codec = if bytes_read >= XZ_MAGIC.len() && prefix[..XZ_MAGIC.len()] == XZ_MAGIC {
+        CompressionCodec::Xz
+    } else if bytes_read >= ZSTD_MAGIC.len() && prefix[..ZSTD_MAGIC.len()] == ZSTD_MAGIC {
+        CompressionCodec::Zstd
+    } else if bytes_read >= BZIP2_MAGIC.len() && prefix[..BZIP2_MAGIC.len()] == BZIP2_MAGIC {
+        CompressionCodec::Bzip2
+    } else if bytes_read >= GZIP_MAGIC.len() && prefix[..GZIP_MAGIC.len()] == GZIP_MAGIC {
+        CompressionCodec::Gzip
+    } else {
+        CompressionCodec::Plain
+    };
+    log::debug!("detected container format: {codec:?}");
+    Ok((
+        codec,
+        Cursor::new(prefix[..bytes_read].to_vec()).chain(reader),
+    ))
+}
+
+/// Deserializes `reader` (already unwrapped of whichever codec it was encoded with) into a
+/// `T`. A failure while *reading* through the decoder (a corrupt compressed body, surfaced by
+/// `serde_json` as an I/O error) is reported as [`FlygFormatError::DecompressionFailed`], while
+/// a failure to *parse* well-formed bytes as JSON is reported as
+/// [`FlygFormatError::FileFormatNotRecognized`], so each codec's own corruption folds into its
+/// own error path instead of being indistinguishable from a bad JSON payload.
+#[cfg(any(
+    feature = "compression",
+    feature = "zstd",
+    feature = "xz",
+    feature = "bzip2"
+))]
+fn deserialize_decoded_json<T: serde::de::DeserializeOwned, R: std::io::Read>(
+    codec_name: &str,
+    reader: R,
+) -> Result<T, FlygFormatError> {
+    serde_json::from_reader(reader).map_err(|error| {
+        if error.is_io() {
+            wrap_error(
+                &format!("decompressing {codec_name} stream"),
+                error,
+                FlygFormatError::DecompressionFailed,
+            )
+        } else {
+            wrap_error(
+                "deserializing JSON",
+                error,
+                FlygFormatError::FileFormatNotRecognized,
+            )
+        }
+    })
+}
+
+/// Decodes `reader` according to `codec` and deserializes the result into a `T`.
+pub(crate) fn deserialize_with_codec<T: serde::de::DeserializeOwned, R: std::io::Read>(
+    codec: CompressionCodec,
+    reader: R,
+) -> Result<T, FlygFormatError> {
+    match codec {
+        CompressionCodec::Plain => {
+            log::debug!("deserializing plain JSON flight data");
+            serde_json::from_reader(reader).map_err(|error| {
+                wrap_error(
+                    "deserializing JSON",
+                    error,
+                    FlygFormatError::FileFormatNotRecognized,
+                )
+            })
+        }
+        #[cfg(feature = "compression")]
+        CompressionCodec::Gzip => {
+            use libflate::gzip::MultiDecoder;
+
+            log::debug!("decompressing gzip flight data");
+            let decoder = MultiDecoder::new(reader).map_err(|error| {
+                wrap_error(
+                    "decompressing gzip stream",
+                    error,
+                    FlygFormatError::DecompressionFailed,
+                )
+            })?;
+            deserialize_decoded_json("gzip", decoder)
+        }
+        #[cfg(not(feature = "compression"))]
+        CompressionCodec::Gzip => {
+            log::warn!("decompressing gzip stream failed: gzip support was not compiled into this build");
+            Err(FlygFormatError::DecompressionFailed(None))
+        }
+        #[cfg(feature = "zstd")]
+        CompressionCodec::Zstd => {
+            log::debug!("decompressing zstd flight data");
+            let decoder = zstd::Decoder::new(reader).map_err(|error| {
+                wrap_error(
+                    "decompressing zstd stream",
+                    error,
+                    FlygFormatError::DecompressionFailed,
+                )
+            })?;
+            deserialize_decoded_json("zstd", decoder)
+        }
+        #[cfg(not(feature = "zstd"))]
+        CompressionCodec::Zstd => {
+            log::warn!("decompressing zstd stream failed: zstd support was not compiled into this build");
+            Err(FlygFormatError::DecompressionFailed(None))
+        }
+        #[cfg(feature = "xz")]
+        CompressionCodec::Xz => {
+            log::debug!("decompressing xz flight data");
+            let decoder = xz2::read::XzDecoder::new(reader);
+            deserialize_decoded_json("xz", decoder)
+        }
+        #[cfg(not(feature = "xz"))]
+        CompressionCodec::Xz => {
+            log::warn!("decompressing xz stream failed: xz support was not compiled into this build");
+            Err(FlygFormatError::DecompressionFailed(None))
+        }
+        #[cfg(feature = "bzip2")]
+        CompressionCodec::Bzip2 => {
+            log::debug!("decompressing bzip2 flight data");
+            let decoder = bzip2::read::BzDecoder::new(reader);
+            deserialize_decoded_json("bzip2", decoder)
+        }
+        #[cfg(not(feature = "bzip2"))]
+        CompressionCodec::Bzip2 => {
+            log::warn!("decompressing bzip2 stream failed: bzip2 support was not compiled into this build");
+            Err(FlygFormatError::DecompressionFailed(None))
+        }
+    }
+}
+
+/// Load stored flight information from any [`Read`](std::io::Read) instance.
+///
+/// Rather than trusting a file extension, the container format is detected by peeking at the
+/// first few bytes of `reader` and matching them against known magic numbers (e.g. `1F 8B`
+/// for gzip, `28 B5 2F FD` for zstd). This means a compressed stream is decoded correctly
+/// even without a file name at all, which lets embedders feed flights straight out of an
+/// archive entry, a network socket or an HTTP body.
+///
+/// # Errors
+/// If the content of the supplied reader is not known to the method, a
+/// [`FlygFormatError::FileFormatNotRecognized`] error will be returned. If the detected codec
+/// was not compiled into this build, a [`FlygFormatError::DecompressionFailed`] error will be
+/// returned instead.
+pub fn load_flight_information_from_reader<R: std::io::Read>(
+    reader: R,
+) -> Result<FlygFlight, FlygFormatError> {
+    let (codec, rechained) = detect_codec(reader).map_err(|error| {
+        wrap_error(
+            "detecting container format",
+            error,
+            FlygFormatError::CouldNotOpenFile,
+        )
+    })?;
+    deserialize_with_codec(codec, rechained)
 }
 
 /// Load stored flight information from a file.
 ///
+/// This is a thin wrapper around [`load_flight_information_from_reader`] which opens
+/// `filename` and hands the buffered file handle to it, so the same magic-number detection
+/// and codec support applies.
+///
 /// # Errors
-/// If the content of the supplied file is not known to the method, a
-/// [`FlygFormatError::FileFormatNotRecognized`] error will be returned.
+/// If the supplied file could not be opened, a [`FlygFormatError::CouldNotOpenFile`] error
+/// will be returned. If the content of the supplied file is not known to the method, a
+/// [`FlygFormatError::FileFormatNotRecognized`] error will be returned. If the detected codec
+/// was not compiled into this build, a [`FlygFormatError::DecompressionFailed`] error will be
+/// returned instead.
 pub fn load_flight_information_from_file(filename: &str) -> Result<FlygFlight, FlygFormatError> {
     use std::io::BufReader;
 
-    // if the file ends with .cflyg, we assume it is compressed and we can redirect the open
-    // request to the corresponding helper method
-    #[cfg(feature = "compression")]
-    if filename
-        .rsplit('.')
-        .next()
-        .map(|ext| ext.eq_ignore_ascii_case("cflyg"))
-        == Some(true)
-    {
-        return load_flight_from_compressed_file(filename);
+    log::debug!("opening flight file '{filename}'");
+    match std::fs::File::open(filename) {
+        Ok(file_handle) => load_flight_information_from_reader(BufReader::new(file_handle)),
+        Err(error) => Err(wrap_error(
+            "opening file",
+            error,
+            FlygFormatError::CouldNotOpenFile,
+        )),
     }
+}
 
-    // for all other cases, we assume its a non-compressed file and we can handle it directly
-    match std::fs::File::open(filename) {
-        Ok(file_handle) => {
-            let buffered_reader = BufReader::new(file_handle);
-            match serde_json::from_reader(buffered_reader) {
-                Ok(read_obj) => Ok(read_obj),
-                Err(_) => Err(FlygFormatError::FileFormatNotRecognized),
-            }
+/// Determines the [`CompressionCodec`] a target file should be written with, based on its
+/// extension: a `.cflyg` file is gzip-compressed, everything else is written as plain JSON.
+pub(crate) fn codec_from_target_extension(filename: &str) -> CompressionCodec {
+    match filename.rsplit('.').next() {
+        Some(ext) if ext.eq_ignore_ascii_case("cflyg") => CompressionCodec::Gzip,
+        _ => CompressionCodec::Plain,
+    }
+}
+
+/// Serializes `value` to JSON and writes it to `writer`, encoding it with `codec` along the
+/// way.
+pub(crate) fn serialize_with_codec<T: Serialize, W: std::io::Write>(
+    value: &T,
+    codec: CompressionCodec,
+    writer: W,
+) -> Result<(), FlygFormatError> {
+    match codec {
+        CompressionCodec::Plain => {
+            log::debug!("serializing plain JSON flight data");
+            serde_json::to_writer(writer, value).map_err(|error| {
+                wrap_error(
+                    "serializing JSON",
+                    error,
+                    FlygFormatError::CouldNotWriteFile,
+                )
+            })
+        }
+        #[cfg(feature = "compression")]
+        CompressionCodec::Gzip => {
+            use libflate::gzip::Encoder;
+
+            log::debug!("compressing flight data with gzip");
+            let mut encoder = Encoder::new(writer).map_err(|error| wrap_error("writing flight", error, FlygFormatError::CouldNotWriteFile))?;
+            serde_json::to_writer(&mut encoder, value).map_err(|error| {
+                wrap_error(
+                    "serializing JSON",
+                    error,
+                    FlygFormatError::CouldNotWriteFile,
+                )
+            })?;
+            encoder.finish().into_result().map_err(|error| wrap_error("writing flight", error, FlygFormatError::CouldNotWriteFile))?;
+            Ok(())
+        }
+        #[cfg(not(feature = "compression"))]
+        CompressionCodec::Gzip => {
+            log::warn!("writing flight failed: gzip support was not compiled into this build");
+            Err(FlygFormatError::CouldNotWriteFile(None))
+        }
+        #[cfg(feature = "zstd")]
+        CompressionCodec::Zstd => {
+            log::debug!("compressing flight data with zstd");
+            let mut encoder = zstd::Encoder::new(writer, 0).map_err(|error| wrap_error("writing flight", error, FlygFormatError::CouldNotWriteFile))?;
+            serde_json::to_writer(&mut encoder, value).map_err(|error| {
+                wrap_error(
+                    "serializing JSON",
+                    error,
+                    FlygFormatError::CouldNotWriteFile,
+                )
+            })?;
+            encoder.finish().map_err(|error| wrap_error("writing flight", error, FlygFormatError::CouldNotWriteFile))?;
+            Ok(())
+        }
+        #[cfg(not(feature = "zstd"))]
+        CompressionCodec::Zstd => {
+            log::warn!("writing flight failed: zstd support was not compiled into this build");
+            Err(FlygFormatError::CouldNotWriteFile(None))
+        }
+        #[cfg(feature = "xz")]
+        CompressionCodec::Xz => {
+            log::debug!("compressing flight data with xz");
+            let mut encoder = xz2::write::XzEncoder::new(writer, 6);
+            serde_json::to_writer(&mut encoder, value).map_err(|error| {
+                wrap_error(
+                    "serializing JSON",
+                    error,
+                    FlygFormatError::CouldNotWriteFile,
+                )
+            })?;
+            encoder.finish().map_err(|error| wrap_error("writing flight", error, FlygFormatError::CouldNotWriteFile))?;
+            Ok(())
+        }
+        #[cfg(not(feature = "xz"))]
+        CompressionCodec::Xz => {
+            log::warn!("writing flight failed: xz support was not compiled into this build");
+            Err(FlygFormatError::CouldNotWriteFile(None))
+        }
+        #[cfg(feature = "bzip2")]
+        CompressionCodec::Bzip2 => {
+            log::debug!("compressing flight data with bzip2");
+            let mut encoder = bzip2::write::BzEncoder::new(writer, bzip2::Compression::default());
+            serde_json::to_writer(&mut encoder, value).map_err(|error| {
+                wrap_error(
+                    "serializing JSON",
+                    error,
+                    FlygFormatError::CouldNotWriteFile,
+                )
+            })?;
+            encoder.finish().map_err(|error| wrap_error("writing flight", error, FlygFormatError::CouldNotWriteFile))?;
+            Ok(())
+        }
+        #[cfg(not(feature = "bzip2"))]
+        CompressionCodec::Bzip2 => {
+            log::warn!("writing flight failed: bzip2 support was not compiled into this build");
+            Err(FlygFormatError::CouldNotWriteFile(None))
         }
-        Err(_) => Err(FlygFormatError::CouldNotOpenFile),
     }
 }
 
+/// Builder controlling how [`save_flight_information_to_file`] writes a flight to disk. By
+/// default the target codec is derived from the file's extension, but it can be overridden
+/// with [`WriteBuilder::compression`] (e.g. to force gzip onto a file without a `.cflyg`
+/// extension).
+#[derive(Default)]
+pub struct WriteBuilder {
+    compression: Option<CompressionCodec>,
+}
+
+impl WriteBuilder {
+    /// Creates a new [`WriteBuilder`] with the target codec derived from the file extension.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the codec that the target file will be encoded with, ignoring its extension.
+    #[must_use]
+    pub fn compression(mut self, codec: CompressionCodec) -> Self {
+        self.compression = Some(codec);
+        self
+    }
+
+    /// Writes `flight` to `filename`, using the overridden codec if one was set or otherwise
+    /// the codec implied by the file's extension.
+    ///
+    /// # Errors
+    /// If the flight could not be serialized or the target file could not be created or
+    /// written to, a [`FlygFormatError::CouldNotWriteFile`] error will be returned.
+    pub fn save(self, flight: &FlygFlight, filename: &str) -> Result<(), FlygFormatError> {
+        let codec = self
+            .compression
+            .unwrap_or_else(|| codec_from_target_extension(filename));
+        log::debug!("writing flight to '{filename}' using codec {codec:?}");
+        let file_handle = std::fs::File::create(filename).map_err(|error| {
+            wrap_error("creating file", error, FlygFormatError::CouldNotWriteFile)
+        })?;
+        serialize_with_codec(flight, codec, file_handle)
+    }
+
+    /// Writes `flight` to any [`Write`](std::io::Write) instance, using the overridden codec
+    /// if one was set or otherwise plain (uncompressed) JSON, since there is no file
+    /// extension to derive a codec from.
+    ///
+    /// # Errors
+    /// If the flight could not be serialized or could not be written to `writer`, a
+    /// [`FlygFormatError::CouldNotWriteFile`] error will be returned.
+    pub fn save_to_writer<W: std::io::Write>(
+        self,
+        flight: &FlygFlight,
+        writer: W,
+    ) -> Result<(), FlygFormatError> {
+        let codec = self.compression.unwrap_or(CompressionCodec::Plain);
+        serialize_with_codec(flight, codec, writer)
+    }
+}
+
+/// Save flight information to a file.
+///
+/// The target container format is derived from the file's extension (`.cflyg` is written as
+/// gzip, everything else as plain JSON). Use [`WriteBuilder`] directly if the codec needs to
+/// be overridden.
+///
+/// # Errors
+/// If the flight could not be serialized or the target file could not be created or written
+/// to, a [`FlygFormatError::CouldNotWriteFile`] error will be returned.
+pub fn save_flight_information_to_file(
+    flight: &FlygFlight,
+    filename: &str,
+) -> Result<(), FlygFormatError> {
+    WriteBuilder::new().save(flight, filename)
+}
+
+/// Save flight information to any [`Write`](std::io::Write) instance as plain, uncompressed
+/// JSON.
+///
+/// This is a thin wrapper around [`WriteBuilder::save_to_writer`] for the common case where
+/// no compression is needed; use [`WriteBuilder`] directly to pick a different codec.
+///
+/// # Errors
+/// If the flight could not be serialized or could not be written to `writer`, a
+/// [`FlygFormatError::CouldNotWriteFile`] error will be returned.
+pub fn save_flight_information_to_writer<W: std::io::Write>(
+    flight: &FlygFlight,
+    writer: W,
+) -> Result<(), FlygFormatError> {
+    WriteBuilder::new().save_to_writer(flight, writer)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{load_flight_information_from_file, FlygFormatError};
+    use crate::{
+        load_flight_information_from_file, load_flight_information_from_reader,
+        save_flight_information_to_file, save_flight_information_to_writer, FlygFlight,
+        FlygFormatError, FuelRecord, PlaneInformation, Times,
+    };
+    #[cfg(feature = "zstd")]
+    use crate::ZSTD_MAGIC;
 
     #[test]
     fn loading_a_non_existing_file_is_handled_correctly() {
@@ -156,7 +581,7 @@ mod tests {
 
         // check if the case was handled gracefully
         assert_eq!(true, result.is_err());
-        assert_eq!(FlygFormatError::CouldNotOpenFile, result.err().unwrap());
+        assert_eq!(FlygFormatError::CouldNotOpenFile(None), result.err().unwrap());
     }
 
     #[test]
@@ -183,4 +608,189 @@ mod tests {
         // check if the file was loaded as expected
         assert_eq!(true, result.is_ok());
     }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn saving_and_loading_a_zstd_compressed_flight_round_trips() {
+        // build a minimal flight to write into an in-memory buffer as zstd
+        let flight = FlygFlight {
+            plane_information: PlaneInformation {
+                name: String::from("Piper PA-28"),
+                fuel_capacity: 48,
+                number_of_engines: 1,
+                fuel_weight: 6.01,
+                unusable_fuel_quantity: 1.0,
+            },
+            landing_speed: 60.0,
+            times: Times {
+                block_off_time: String::from("2024-02-01T09:00:00Z"),
+                block_on_time: String::from("2024-02-01T10:15:00Z"),
+                landing_time: String::from("2024-02-01T10:10:00Z"),
+                takeoff_time: String::from("2024-02-01T09:05:00Z"),
+            },
+            fuel_records: vec![FuelRecord { fuel_quantity: 30.0 }],
+        };
+        let mut buffer = Vec::new();
+
+        // write the flight as zstd into the buffer and read it back without touching the filesystem
+        let save_result = crate::WriteBuilder::new()
+            .compression(crate::CompressionCodec::Zstd)
+            .save_to_writer(&flight, &mut buffer);
+        let load_result = load_flight_information_from_reader(buffer.as_slice());
+
+        // check that the round trip preserved the data
+        assert!(save_result.is_ok());
+        assert!(load_result.is_ok());
+        assert_eq!(
+            flight.plane_information.name,
+            load_result.unwrap().plane_information.name
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn loading_a_corrupt_zstd_stream_is_reported_as_a_decompression_failure() {
+        // a valid zstd magic number followed by garbage instead of a real zstd frame
+        let mut corrupt_stream = ZSTD_MAGIC.to_vec();
+        corrupt_stream.extend_from_slice(&[0xff; 16]);
+
+        // try to load the corrupt stream
+        let result = load_flight_information_from_reader(corrupt_stream.as_slice());
+
+        // the corruption should be attributed to decompression, not to the JSON payload
+        assert!(result.is_err());
+        match result.err().unwrap() {
+            FlygFormatError::DecompressionFailed(_) => {}
+            other => panic!("expected a DecompressionFailed error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn saving_and_loading_a_flight_round_trips() {
+        // build a minimal flight to write to disk
+        let flight = FlygFlight {
+            plane_information: PlaneInformation {
+                name: String::from("Cessna 172"),
+                fuel_capacity: 56,
+                number_of_engines: 1,
+                fuel_weight: 6.01,
+                unusable_fuel_quantity: 1.5,
+            },
+            landing_speed: 65.0,
+            times: Times {
+                block_off_time: String::from("2024-01-01T10:00:00Z"),
+                block_on_time: String::from("2024-01-01T11:30:00Z"),
+                landing_time: String::from("2024-01-01T11:25:00Z"),
+                takeoff_time: String::from("2024-01-01T10:05:00Z"),
+            },
+            fuel_records: vec![FuelRecord { fuel_quantity: 40.0 }],
+        };
+        let path_to_output_file = std::env::temp_dir().join("flyg-format-round-trip-test.flyg");
+        let path_to_output_file = path_to_output_file.to_str().unwrap();
+
+        // save the flight and load it back in
+        let save_result = save_flight_information_to_file(&flight, path_to_output_file);
+        let load_result = load_flight_information_from_file(path_to_output_file);
+        let _ = std::fs::remove_file(path_to_output_file);
+
+        // check that the round trip preserved the data
+        assert!(save_result.is_ok());
+        assert!(load_result.is_ok());
+        assert_eq!(
+            flight.plane_information.name,
+            load_result.unwrap().plane_information.name
+        );
+    }
+
+    #[test]
+    fn saving_and_loading_a_flight_through_an_in_memory_buffer_works() {
+        // build a minimal flight to write into an in-memory buffer
+        let flight = FlygFlight {
+            plane_information: PlaneInformation {
+                name: String::from("Piper PA-28"),
+                fuel_capacity: 48,
+                number_of_engines: 1,
+                fuel_weight: 6.01,
+                unusable_fuel_quantity: 1.0,
+            },
+            landing_speed: 60.0,
+            times: Times {
+                block_off_time: String::from("2024-02-01T09:00:00Z"),
+                block_on_time: String::from("2024-02-01T10:15:00Z"),
+                landing_time: String::from("2024-02-01T10:10:00Z"),
+                takeoff_time: String::from("2024-02-01T09:05:00Z"),
+            },
+            fuel_records: vec![FuelRecord { fuel_quantity: 30.0 }],
+        };
+        let mut buffer = Vec::new();
+
+        // write the flight into the buffer and read it back without touching the filesystem
+        let save_result = save_flight_information_to_writer(&flight, &mut buffer);
+        let load_result = load_flight_information_from_reader(buffer.as_slice());
+
+        // check that the round trip preserved the data
+        assert!(save_result.is_ok());
+        assert!(load_result.is_ok());
+        assert_eq!(
+            flight.plane_information.name,
+            load_result.unwrap().plane_information.name
+        );
+    }
+
+    /// A [`Read`](std::io::Read) wrapper that only ever returns a single byte per call, standing
+    /// in for a slow socket or chunked HTTP body that never fills the caller's buffer in one go.
+    struct OneByteAtATimeReader<R> {
+        inner: R,
+    }
+
+    impl<R: std::io::Read> std::io::Read for OneByteAtATimeReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if buf.is_empty() {
+                return Ok(0);
+            }
+            self.inner.read(&mut buf[..1])
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn loading_a_compressed_flight_through_a_slow_reader_still_detects_the_codec() {
+        // write a flight as gzip into an in-memory buffer
+        let flight = FlygFlight {
+            plane_information: PlaneInformation {
+                name: String::from("Diamond DA40"),
+                fuel_capacity: 50,
+                number_of_engines: 1,
+                fuel_weight: 6.01,
+                unusable_fuel_quantity: 1.0,
+            },
+            landing_speed: 58.0,
+            times: Times {
+                block_off_time: String::from("2024-03-01T09:00:00Z"),
+                block_on_time: String::from("2024-03-01T10:15:00Z"),
+                landing_time: String::from("2024-03-01T10:10:00Z"),
+                takeoff_time: String::from("2024-03-01T09:05:00Z"),
+            },
+            fuel_records: vec![FuelRecord { fuel_quantity: 35.0 }],
+        };
+        let mut buffer = Vec::new();
+        crate::WriteBuilder::new()
+            .compression(crate::CompressionCodec::Gzip)
+            .save_to_writer(&flight, &mut buffer)
+            .unwrap();
+
+        // load it back through a reader that never hands back more than one byte per call, the
+        // same way a socket or chunked HTTP body would
+        let slow_reader = OneByteAtATimeReader {
+            inner: buffer.as_slice(),
+        };
+        let load_result = load_flight_information_from_reader(slow_reader);
+
+        // the gzip magic number must still be detected despite the short reads
+        assert!(load_result.is_ok());
+        assert_eq!(
+            flight.plane_information.name,
+            load_result.unwrap().plane_information.name
+        );
+    }
 }